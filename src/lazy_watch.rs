@@ -0,0 +1,98 @@
+use crate::OnceWatch;
+use std::{cell::UnsafeCell, future::Future};
+
+/// An asynchronous cell that lazily initializes itself from a stored future.
+///
+/// This is the async analogue of [`std::sync::LazyLock`]: the first task that calls
+/// [`get()`](LazyWatch::get) runs the closure passed to [`new()`](LazyWatch::new) and
+/// every other task, including concurrent ones, waits for that result and then shares
+/// the cached `&T`. It builds on [`OnceWatch::get_or_init`] so call sites never need to
+/// spawn a separate writer task.
+///
+/// If a caller drops the `get()` future before it completes (for example, a timeout
+/// races it) or the initializer panics, the stored closure is left in place so that a
+/// later `get()` call retries it, the same way `OnceWatch::get_or_init` retries.
+///
+/// ```rust
+/// use async_once_watch::LazyWatch;
+/// use once_cell::sync::Lazy;
+/// use std::{future::Future, pin::Pin};
+///
+/// # async_std::task::block_on(async {
+/// static LAZY: Lazy<LazyWatch<u8, fn() -> Pin<Box<dyn Future<Output = u8> + Send>>>> =
+///     Lazy::new(|| LazyWatch::new(|| Box::pin(async { 10 })));
+///
+/// let value = LAZY.get().await;
+/// assert_eq!(*value, 10);
+/// # });
+/// ```
+pub struct LazyWatch<T, F = fn() -> T>
+where
+    T: Sync,
+{
+    watch: OnceWatch<T>,
+    init: UnsafeCell<Option<F>>,
+}
+
+impl<T, F, Fut> LazyWatch<T, F>
+where
+    T: Sync,
+    F: Fn() -> Fut,
+    Fut: Future<Output = T>,
+{
+    /// Creates a new `LazyWatch` that will initialize itself with `init` on first use.
+    pub fn new(init: F) -> Self {
+        Self {
+            watch: OnceWatch::new(),
+            init: UnsafeCell::new(Some(init)),
+        }
+    }
+
+    /// Waits until the value is ready, running the stored initializer on first call.
+    ///
+    /// The initializer is only removed once it has returned a value; if the winning
+    /// task's call is cancelled or panics beforehand, it is left in place so the next
+    /// task to win the race can retry it.
+    pub async fn get(&self) -> &T {
+        self.watch
+            .get_or_init(async {
+                // SAFETY: `OnceWatch::get_or_init` only polls this future in the single
+                // task that won the CAS race to initialize the value, so we have
+                // exclusive access to `init` here.
+                let value = {
+                    let init = unsafe { (*self.init.get()).as_ref() }
+                        .expect("LazyWatch initializer missing");
+                    init().await
+                };
+
+                // Only clear the initializer now that it has actually produced a
+                // value, so a cancelled or panicking attempt leaves it in place for
+                // the next caller to retry.
+                unsafe {
+                    *self.init.get() = None;
+                }
+
+                value
+            })
+            .await
+    }
+
+    /// Try to get the value reference in non-blocking manner.
+    ///
+    /// It returns `None` if the value is not ready.
+    pub fn try_get(&self) -> Option<&T> {
+        self.watch.try_get()
+    }
+}
+
+// SAFETY: `init` is only ever read or written by the single task that wins the
+// initialization race inside `OnceWatch::get_or_init`, so sharing a `&LazyWatch`
+// across threads is sound as long as the initializer itself is `Send`. The produced
+// `T` has the same "written on one thread, read or dropped on another" requirement as
+// `OnceWatch<T>`'s own `Sync` impl, so `T: Send` is needed here too.
+unsafe impl<T, F> Sync for LazyWatch<T, F>
+where
+    T: Send + Sync,
+    F: Send,
+{
+}
@@ -31,23 +31,64 @@
 //!     assert_eq!(received, secret);
 //! });
 //! ```
+//!
+//! If the value should simply be computed on first access instead of written by a
+//! separate task, [`LazyWatch<T>`](LazyWatch) avoids the boilerplate above entirely:
+//!
+//! ```rust
+//! use async_once_watch::LazyWatch;
+//! use once_cell::sync::Lazy;
+//! use std::{future::Future, pin::Pin};
+//!
+//! # async_std::task::block_on(async {
+//! static LAZY: Lazy<LazyWatch<u8, fn() -> Pin<Box<dyn Future<Output = u8> + Send>>>> =
+//!     Lazy::new(|| LazyWatch::new(|| Box::pin(async { 10 })));
+//!
+//! let value = LAZY.get().await;
+//! assert_eq!(*value, 10);
+//! # });
+//! ```
+
+mod lazy_watch;
+
+pub use lazy_watch::LazyWatch;
 
 use event_listener::Event;
 use std::{
-    ptr,
-    sync::atomic::{AtomicPtr, Ordering::*},
+    cell::UnsafeCell,
+    fmt,
+    future::Future,
+    mem::MaybeUninit,
+    sync::atomic::{AtomicUsize, Ordering::*},
+    time::{Duration, Instant},
 };
 
+/// No writer has claimed the slot yet.
+const UNINITIALIZED: usize = 0;
+/// A writer is currently running its initializer.
+const INITIALIZING: usize = 1;
+/// The value is ready to be read.
+const INITIALIZED: usize = 2;
+
 /// The shareable container which value is set once.
-#[derive(Debug)]
 pub struct OnceWatch<T>
 where
     T: Sync,
 {
     event: Event,
-    data: AtomicPtr<T>,
+    state: AtomicUsize,
+    data: UnsafeCell<MaybeUninit<T>>,
 }
 
+// SAFETY: access to `data` is only ever granted once `state` is observed to be
+// `INITIALIZED` (or, for `&mut self` methods, gated by Rust's exclusive borrow), so
+// handing out `&T` to multiple threads concurrently is sound given `T: Sync`. But the
+// value is also typically written by whichever task wins `set`/`get_or_init` and then
+// read or dropped (via `assume_init_drop`) by a different thread once the container is
+// shared — the same "send across threads, then use" pattern `Mutex<T>` and
+// `OnceLock<T>` require `T: Send` for — so `T: Send` is needed too.
+unsafe impl<T> Sync for OnceWatch<T> where T: Send + Sync {}
+
 impl<T> OnceWatch<T>
 where
     T: Sync,
@@ -57,7 +98,8 @@ where
         let event = Event::new();
         Self {
             event,
-            data: AtomicPtr::new(ptr::null_mut()),
+            state: AtomicUsize::new(UNINITIALIZED),
+            data: UnsafeCell::new(MaybeUninit::uninit()),
         }
     }
 
@@ -65,39 +107,215 @@ where
     ///
     /// It returns `Ok` in first call and `Err` in later calls.
     pub fn set(&self, data: T) -> Result<(), T> {
-        let data = Box::new(data);
-        let ptr = Box::into_raw(data);
+        if self
+            .state
+            .compare_exchange(UNINITIALIZED, INITIALIZING, AcqRel, Relaxed)
+            .is_err()
+        {
+            return Err(data);
+        }
 
-        let result = self
-            .data
-            .compare_exchange(ptr::null_mut(), ptr, AcqRel, Relaxed);
+        // SAFETY: only the caller whose `UNINITIALIZED -> INITIALIZING` CAS succeeds
+        // (here or in `get_or_try_init`) may write `data`, and that is us right now.
+        unsafe {
+            (*self.data.get()).write(data);
+        }
+        self.state.store(INITIALIZED, Release);
+        self.event.notify_additional(usize::MAX);
+        Ok(())
+    }
 
-        match result {
-            Ok(_) => {
-                self.event.notify_additional(usize::MAX);
-                Ok(())
-            }
-            Err(_) => {
-                let data = unsafe { *Box::from_raw(ptr) };
-                Err(data)
+    /// Waits until the value is ready, initializing it with `init` if no one else has.
+    ///
+    /// Only one caller across all waiting tasks actually polls `init`; every other caller
+    /// waits for that result and then shares the same reference. If the winning task's
+    /// future panics or is dropped before completing, the slot is released so that a later
+    /// call can retry the initialization.
+    pub async fn get_or_init<F>(&self, init: F) -> &T
+    where
+        F: Future<Output = T>,
+    {
+        match self
+            .get_or_try_init(async move { Ok::<T, std::convert::Infallible>(init.await) })
+            .await
+        {
+            Ok(value) => value,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Like [`get_or_init`](Self::get_or_init), but the initializer may fail.
+    ///
+    /// If `init` returns `Err`, the slot is released so that a later call can retry.
+    pub async fn get_or_try_init<F, E>(&self, init: F) -> Result<&T, E>
+    where
+        F: Future<Output = Result<T, E>>,
+    {
+        loop {
+            match self
+                .state
+                .compare_exchange(UNINITIALIZED, INITIALIZING, AcqRel, Acquire)
+            {
+                Ok(_) => {
+                    let mut guard = InitGuard {
+                        watch: self,
+                        completed: false,
+                    };
+                    let value = init.await?;
+
+                    // SAFETY: see the comment in `set()`.
+                    unsafe {
+                        (*self.data.get()).write(value);
+                    }
+                    self.state.store(INITIALIZED, Release);
+                    guard.completed = true;
+                    self.event.notify_additional(usize::MAX);
+
+                    return Ok(self.try_get().expect("state is INITIALIZED"));
+                }
+                Err(INITIALIZED) => {
+                    return Ok(self.try_get().expect("state is INITIALIZED"));
+                }
+                Err(_) => {
+                    let listener = self.event.listen();
+                    if self.state.load(Acquire) == INITIALIZED {
+                        continue;
+                    }
+                    listener.await;
+                }
             }
         }
     }
 
     /// Waits until the value is set and obtains the reference.
+    ///
+    /// A single notification on the event does not always mean the value is ready —
+    /// a rolled-back `get_or_init`/`get_or_try_init` wakes one waiter to retry the CAS
+    /// race, not because the value was set — so this loops and rechecks the state the
+    /// same way [`wait()`](Self::wait) does.
     pub async fn get(&self) -> &T {
-        let listener = self.event.listen();
-        listener.await;
-        let ptr = self.data.load(Acquire);
-        unsafe { ptr.as_ref().unwrap() }
+        loop {
+            if let Some(value) = self.try_get() {
+                return value;
+            }
+
+            let listener = self.event.listen();
+
+            if let Some(value) = self.try_get() {
+                return value;
+            }
+
+            listener.await;
+        }
+    }
+
+    /// Blocks the current thread until the value is set and obtains the reference.
+    ///
+    /// This is the synchronous counterpart to [`get()`](Self::get), for consumers that
+    /// are not running inside an async executor. It parks the thread on the same
+    /// [`Event`] used by the async API instead of spinning.
+    pub fn wait(&self) -> &T {
+        loop {
+            if let Some(value) = self.try_get() {
+                return value;
+            }
+
+            let listener = self.event.listen();
+
+            if let Some(value) = self.try_get() {
+                return value;
+            }
+
+            listener.wait();
+        }
+    }
+
+    /// Like [`wait()`](Self::wait), but gives up and returns `None` after `timeout`.
+    pub fn wait_timeout(&self, timeout: Duration) -> Option<&T> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            if let Some(value) = self.try_get() {
+                return Some(value);
+            }
+
+            let listener = self.event.listen();
+
+            if let Some(value) = self.try_get() {
+                return Some(value);
+            }
+
+            if !listener.wait_deadline(deadline) {
+                return None;
+            }
+        }
     }
 
     /// Try to get the value reference in non-blocking manner.
     ///
     /// It returns `None` if the value is not ready.
     pub fn try_get(&self) -> Option<&T> {
-        let ptr = self.data.load(Acquire);
-        unsafe { ptr.as_ref() }
+        if self.state.load(Acquire) == INITIALIZED {
+            // SAFETY: `state` is only set to `INITIALIZED` after `data` has been
+            // written, and the `Acquire` load above pairs with the `Release` store
+            // that set it, so the write is visible here.
+            Some(unsafe { (*self.data.get()).assume_init_ref() })
+        } else {
+            None
+        }
+    }
+
+    /// Gets a mutable reference to the value, if it is set.
+    ///
+    /// Since this takes `&mut self`, no concurrent `get()` can be outstanding, so this
+    /// does not need to touch the event or go through `Acquire`/`Release`.
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        if *self.state.get_mut() == INITIALIZED {
+            Some(unsafe { (*self.data.get()).assume_init_mut() })
+        } else {
+            None
+        }
+    }
+
+    /// Takes the value out of the container, leaving it uninitialized again.
+    ///
+    /// Returns `None` if the value was not set. Like [`get_mut`](Self::get_mut), this
+    /// requires `&mut self`, so it is safe to reuse the container for a later `set()` or
+    /// `get_or_init()` once this returns.
+    pub fn take(&mut self) -> Option<T> {
+        let previous_state = std::mem::replace(self.state.get_mut(), UNINITIALIZED);
+
+        if previous_state == INITIALIZED {
+            Some(unsafe { (*self.data.get()).assume_init_read() })
+        } else {
+            None
+        }
+    }
+
+    /// Consumes the container, returning the value if it was set.
+    pub fn into_inner(mut self) -> Option<T> {
+        self.take()
+    }
+}
+
+/// Rolls a failed or abandoned initialization back to `UNINITIALIZED` unless it completed.
+struct InitGuard<'a, T>
+where
+    T: Sync,
+{
+    watch: &'a OnceWatch<T>,
+    completed: bool,
+}
+
+impl<T> Drop for InitGuard<'_, T>
+where
+    T: Sync,
+{
+    fn drop(&mut self) {
+        if !self.completed {
+            self.watch.state.store(UNINITIALIZED, Release);
+            self.watch.event.notify(1);
+        }
     }
 }
 
@@ -115,11 +333,22 @@ where
     T: Sync,
 {
     fn drop(&mut self) {
-        let ptr = self.data.load(Acquire);
-        if !ptr.is_null() {
+        if *self.state.get_mut() == INITIALIZED {
             unsafe {
-                drop(Box::from_raw(ptr));
+                (*self.data.get()).assume_init_drop();
             }
         }
     }
 }
+
+impl<T> fmt::Debug for OnceWatch<T>
+where
+    T: Sync + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.try_get() {
+            Some(value) => f.debug_tuple("OnceWatch").field(value).finish(),
+            None => f.write_str("OnceWatch(Uninit)"),
+        }
+    }
+}
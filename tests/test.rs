@@ -1,8 +1,12 @@
-use async_once_watch::OnceWatch;
+use async_once_watch::{LazyWatch, OnceWatch};
 use async_std::task::{sleep, spawn};
-use futures::future;
+use futures::{future, FutureExt};
 use once_cell::sync::Lazy;
 use rand::prelude::*;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 
 #[async_std::test]
@@ -42,3 +46,182 @@ async fn pubsub_test() {
         future::join_all(slow_consumers)
     );
 }
+
+#[async_std::test]
+async fn get_or_init_runs_initializer_once_test() {
+    static STATE: Lazy<OnceWatch<u8>> = Lazy::new(OnceWatch::new);
+    static INIT_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    let secret: u8 = rand::thread_rng().gen();
+
+    let callers = (0..16).map(|_| {
+        spawn(async move {
+            let received = *STATE
+                .get_or_init(async {
+                    sleep(Duration::from_millis(100)).await;
+                    INIT_COUNT.fetch_add(1, Ordering::SeqCst);
+                    secret
+                })
+                .await;
+            assert_eq!(received, secret);
+        })
+    });
+
+    future::join_all(callers).await;
+    assert_eq!(INIT_COUNT.load(Ordering::SeqCst), 1);
+}
+
+#[async_std::test]
+async fn get_or_try_init_retries_after_failure_test() {
+    static STATE: Lazy<OnceWatch<u8>> = Lazy::new(OnceWatch::new);
+
+    let err = STATE
+        .get_or_try_init(async { Err::<u8, _>("boom") })
+        .await;
+    assert_eq!(err, Err("boom"));
+
+    let secret: u8 = rand::thread_rng().gen();
+    let value = STATE.get_or_try_init(async { Ok::<_, &str>(secret) }).await;
+    assert_eq!(value, Ok(&secret));
+}
+
+#[async_std::test]
+async fn get_or_init_retries_after_panic_test() {
+    static STATE: Lazy<OnceWatch<u8>> = Lazy::new(OnceWatch::new);
+
+    let panicked = std::panic::AssertUnwindSafe(STATE.get_or_init(async {
+        panic!("initializer panics");
+        #[allow(unreachable_code)]
+        0u8
+    }))
+    .catch_unwind()
+    .await;
+    assert!(panicked.is_err());
+
+    let secret: u8 = rand::thread_rng().gen();
+    let value = *STATE.get_or_init(async { secret }).await;
+    assert_eq!(value, secret);
+}
+
+#[async_std::test]
+async fn lazy_watch_test() {
+    static LAZY: Lazy<LazyWatch<u8, fn() -> Pin<Box<dyn Future<Output = u8> + Send>>>> =
+        Lazy::new(|| {
+            LazyWatch::new(|| {
+                Box::pin(async {
+                    sleep(Duration::from_millis(100)).await;
+                    42
+                })
+            })
+        });
+
+    let callers = (0..16).map(|_| spawn(async move { *LAZY.get().await }));
+    let results = future::join_all(callers).await;
+
+    assert!(results.into_iter().all(|value| value == 42));
+}
+
+#[async_std::test]
+async fn lazy_watch_retries_after_panic_test() {
+    static ATTEMPTS: AtomicUsize = AtomicUsize::new(0);
+    static LAZY: Lazy<LazyWatch<u8, fn() -> Pin<Box<dyn Future<Output = u8> + Send>>>> =
+        Lazy::new(|| {
+            LazyWatch::new(|| {
+                Box::pin(async {
+                    if ATTEMPTS.fetch_add(1, Ordering::SeqCst) == 0 {
+                        panic!("first attempt fails");
+                    }
+                    42
+                })
+            })
+        });
+
+    let first = std::panic::AssertUnwindSafe(LAZY.get()).catch_unwind().await;
+    assert!(first.is_err());
+
+    let second = *LAZY.get().await;
+    assert_eq!(second, 42);
+}
+
+#[test]
+fn take_and_into_inner_test() {
+    let mut watch = OnceWatch::new();
+    assert_eq!(watch.get_mut(), None);
+    assert_eq!(watch.take(), None);
+
+    assert!(watch.set(5u8).is_ok());
+    assert_eq!(watch.get_mut(), Some(&mut 5));
+    assert_eq!(watch.take(), Some(5));
+    assert_eq!(watch.take(), None);
+
+    // The container is reusable after `take()`.
+    assert!(watch.set(6u8).is_ok());
+    assert_eq!(watch.into_inner(), Some(6));
+}
+
+#[test]
+fn wait_blocks_until_set_test() {
+    let watch = Arc::new(OnceWatch::new());
+    let secret: u8 = rand::thread_rng().gen();
+
+    assert_eq!(watch.wait_timeout(Duration::from_millis(50)), None);
+
+    let writer = {
+        let watch = Arc::clone(&watch);
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(200));
+            assert!(watch.set(secret).is_ok());
+        })
+    };
+
+    assert_eq!(*watch.wait(), secret);
+    writer.join().unwrap();
+}
+
+// Exercises the `state`/`data` race directly with OS threads rather than an async
+// executor, so it is also useful to run under Miri (`cargo miri test`) to catch any
+// undefined behavior in the unsafe `MaybeUninit` accesses. A reader thread polls
+// `try_get()` concurrently with the racing writers so Miri actually has a read/write
+// race on the `UnsafeCell`/`MaybeUninit` access to check, not just writer-vs-writer.
+#[test]
+fn concurrent_set_get_race_test() {
+    let watch = Arc::new(OnceWatch::new());
+    let barrier = Arc::new(std::sync::Barrier::new(8));
+    let done = Arc::new(std::sync::atomic::AtomicBool::new(false));
+
+    let reader = {
+        let watch = Arc::clone(&watch);
+        let done = Arc::clone(&done);
+        std::thread::spawn(move || {
+            while !done.load(Ordering::Relaxed) {
+                if let Some(value) = watch.try_get() {
+                    assert!(*value < 8);
+                }
+                std::thread::yield_now();
+            }
+        })
+    };
+
+    let writers: Vec<_> = (0..8u8)
+        .map(|i| {
+            let watch = Arc::clone(&watch);
+            let barrier = Arc::clone(&barrier);
+            std::thread::spawn(move || {
+                barrier.wait();
+                watch.set(i).is_ok()
+            })
+        })
+        .collect();
+
+    let successes: usize = writers
+        .into_iter()
+        .map(|handle| handle.join().unwrap())
+        .filter(|ok| *ok)
+        .count();
+
+    done.store(true, Ordering::Relaxed);
+    reader.join().unwrap();
+
+    assert_eq!(successes, 1);
+    assert!(watch.try_get().is_some());
+}